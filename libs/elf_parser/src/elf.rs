@@ -0,0 +1,264 @@
+extern crate posix;
+use posix::Errno;
+
+extern crate stpack;
+use stpack::{unpacker, Unpacker};
+
+use crate::err::ElfParserError;
+use crate::ident::{ElfClass, ElfEndian};
+use crate::phdr::ProgramHeaderIterator;
+use crate::reloc::RelocationIterator;
+use crate::sections::{self, ElfSection};
+use crate::symbol::SymtabKind;
+use crate::symtab::SymtabIterator;
+
+const ELFMAG: &[u8; 4] = b"\x7fELF";
+const EI_CLASS: usize = 4;
+const EI_DATA: usize = 5;
+const ELFCLASS32: u8 = 1;
+const ELFCLASS64: u8 = 2;
+const ELFDATA2LSB: u8 = 1;
+const ELFDATA2MSB: u8 = 2;
+
+unpacker! {
+    pub struct Elf32Ehdr {
+        pub e_ident: [u8; 16],
+        pub e_type: u16,
+        pub e_machine: u16,
+        pub e_version: u32,
+        pub e_entry: u32,
+        pub e_phoff: u32,
+        pub e_shoff: u32,
+        pub e_flags: u32,
+        pub e_ehsize: u16,
+        pub e_phentsize: u16,
+        pub e_phnum: u16,
+        pub e_shentsize: u16,
+        pub e_shnum: u16,
+        pub e_shstrndx: u16,
+    }
+}
+
+unpacker! {
+    pub struct Elf64Ehdr {
+        pub e_ident: [u8; 16],
+        pub e_type: u16,
+        pub e_machine: u16,
+        pub e_version: u32,
+        pub e_entry: u64,
+        pub e_phoff: u64,
+        pub e_shoff: u64,
+        pub e_flags: u32,
+        pub e_ehsize: u16,
+        pub e_phentsize: u16,
+        pub e_phnum: u16,
+        pub e_shentsize: u16,
+        pub e_shnum: u16,
+        pub e_shstrndx: u16,
+    }
+}
+
+/// Auto-detecting ELF front-end: reads `e_ident` to pick the 32/64-bit
+/// and endianness variant of the header, then exposes `symbols()`,
+/// `sections()` and `segments()` over the rest of the file without the
+/// caller needing to know which class it parsed.
+#[derive(Debug)]
+pub struct Elf<'a> {
+    data: &'a [u8],
+    class: ElfClass,
+    endian: ElfEndian,
+    sections: Vec<ElfSection<'a>>,
+    phoff: u64,
+    phentsize: u16,
+    phnum: u16,
+}
+
+impl<'a> Elf<'a> {
+    pub fn parse(data: &'a [u8]) -> Result<Self, ElfParserError> {
+        if data.len() < EI_DATA + 1 || &data[0..4] != ELFMAG {
+            return Err(ElfParserError::new(
+                Errno::EINVAL, String::from("Not an ELF file (bad magic)")));
+        }
+
+        let class = match data[EI_CLASS] {
+            ELFCLASS32 => ElfClass::Elf32,
+            ELFCLASS64 => ElfClass::Elf64,
+            other => return Err(ElfParserError::new(
+                Errno::EINVAL, format!("Unknown EI_CLASS: {}", other))),
+        };
+
+        let endian = match data[EI_DATA] {
+            ELFDATA2LSB => ElfEndian::ElfLE,
+            ELFDATA2MSB => ElfEndian::ElfBE,
+            other => return Err(ElfParserError::new(
+                Errno::EINVAL, format!("Unknown EI_DATA: {}", other))),
+        };
+
+        let le = endian == ElfEndian::ElfLE;
+
+        let (phoff, phentsize, phnum, shoff, shentsize, shnum, shstrndx) = match class {
+            ElfClass::Elf32 => match Elf32Ehdr::unpack(data, le) {
+                Ok((eh, _)) => (
+                    eh.e_phoff as u64,
+                    eh.e_phentsize,
+                    eh.e_phnum,
+                    eh.e_shoff as u64,
+                    eh.e_shentsize,
+                    eh.e_shnum,
+                    eh.e_shstrndx,
+                ),
+                Err(_) => return Err(ElfParserError::new(
+                    Errno::EINVAL, String::from("Failed to parse ELF header"))),
+            },
+            ElfClass::Elf64 => match Elf64Ehdr::unpack(data, le) {
+                Ok((eh, _)) => (
+                    eh.e_phoff,
+                    eh.e_phentsize,
+                    eh.e_phnum,
+                    eh.e_shoff,
+                    eh.e_shentsize,
+                    eh.e_shnum,
+                    eh.e_shstrndx,
+                ),
+                Err(_) => return Err(ElfParserError::new(
+                    Errno::EINVAL, String::from("Failed to parse ELF header"))),
+            },
+        };
+
+        let sections = sections::parse(
+            data, class, endian, shoff, shnum, shentsize, shstrndx)?;
+
+        Ok(Self {
+            data,
+            class,
+            endian,
+            sections,
+            phoff,
+            phentsize,
+            phnum,
+        })
+    }
+
+    pub fn symbols(&self) -> SymtabIterator<'_> {
+        SymtabIterator::new(self.class, self.endian, &self.sections)
+    }
+
+    /// Like `symbols`, but restricted to the static symbol table (`SHT_SYMTAB`).
+    pub fn static_symbols(&self) -> SymtabIterator<'_> {
+        SymtabIterator::new_of_kind(self.class, self.endian, &self.sections, SymtabKind::Symtab)
+    }
+
+    /// Like `symbols`, but restricted to the dynamic symbol table (`SHT_DYNSYM`).
+    pub fn dynamic_symbols(&self) -> SymtabIterator<'_> {
+        SymtabIterator::new_of_kind(self.class, self.endian, &self.sections, SymtabKind::Dynsym)
+    }
+
+    pub fn sections(&self) -> &[ElfSection<'a>] {
+        &self.sections
+    }
+
+    pub fn segments(&self) -> ProgramHeaderIterator<'_> {
+        let table = self.data.get(self.phoff as usize..).unwrap_or(&[]);
+        ProgramHeaderIterator::new(
+            self.class, self.endian, table, self.phentsize as u64, self.phnum as u64,
+            self.data.len() as u64)
+    }
+
+    pub fn relocations(&self) -> RelocationIterator<'_> {
+        RelocationIterator::new(self.class, self.endian, &self.sections)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::Elf;
+
+    fn minimal_elf32le() -> Vec<u8> {
+        let mut data = Vec::new();
+
+        // e_ident
+        data.extend_from_slice(b"\x7fELF");
+        data.push(1);              // EI_CLASS = ELFCLASS32
+        data.push(1);              // EI_DATA = ELFDATA2LSB
+        data.extend_from_slice(&[0u8; 10]);
+
+        data.extend_from_slice(&0u16.to_le_bytes());   // e_type
+        data.extend_from_slice(&0u16.to_le_bytes());   // e_machine
+        data.extend_from_slice(&0u32.to_le_bytes());   // e_version
+        data.extend_from_slice(&0u32.to_le_bytes());   // e_entry
+        data.extend_from_slice(&0u32.to_le_bytes());   // e_phoff
+        data.extend_from_slice(&52u32.to_le_bytes());  // e_shoff
+        data.extend_from_slice(&0u32.to_le_bytes());   // e_flags
+        data.extend_from_slice(&52u16.to_le_bytes());  // e_ehsize
+        data.extend_from_slice(&0u16.to_le_bytes());   // e_phentsize
+        data.extend_from_slice(&0u16.to_le_bytes());   // e_phnum
+        data.extend_from_slice(&40u16.to_le_bytes());  // e_shentsize
+        data.extend_from_slice(&1u16.to_le_bytes());   // e_shnum
+        data.extend_from_slice(&0u16.to_le_bytes());   // e_shstrndx
+
+        assert_eq!(data.len(), 52);
+
+        // single .shstrtab section header, at offset 52
+        data.extend_from_slice(&0u32.to_le_bytes());   // name
+        data.extend_from_slice(&3u32.to_le_bytes());   // typ = SHT_STRTAB
+        data.extend_from_slice(&0u32.to_le_bytes());   // flags
+        data.extend_from_slice(&0u32.to_le_bytes());   // addr
+        data.extend_from_slice(&92u32.to_le_bytes());  // offset
+        data.extend_from_slice(&11u32.to_le_bytes());  // size
+        data.extend_from_slice(&0u32.to_le_bytes());   // link
+        data.extend_from_slice(&0u32.to_le_bytes());   // info
+        data.extend_from_slice(&0u32.to_le_bytes());   // addralign
+        data.extend_from_slice(&0u32.to_le_bytes());   // entsize
+
+        assert_eq!(data.len(), 92);
+
+        // shstrtab content
+        data.push(0);
+        data.extend_from_slice(b".shstrtab\0");
+
+        assert_eq!(data.len(), 103);
+
+        data
+    }
+
+    #[test]
+    fn parses_minimal_elf32le_header_and_sections() {
+        let data = minimal_elf32le();
+        let elf = Elf::parse(&data).unwrap();
+
+        assert_eq!(elf.sections().len(), 1);
+        assert_eq!(elf.sections()[0].name, "");
+        assert_eq!(elf.symbols().count(), 0);
+        assert_eq!(elf.segments().count(), 0);
+    }
+
+    #[test]
+    fn rejects_bad_magic() {
+        let mut data = minimal_elf32le();
+        data[0] = 0;
+
+        Elf::parse(&data).expect_err("Parsing ELF with bad magic unexpectedly succeeded");
+    }
+
+    #[test]
+    fn rejects_unknown_class() {
+        let mut data = minimal_elf32le();
+        data[4] = 3;
+
+        Elf::parse(&data).expect_err("Parsing ELF with unknown EI_CLASS unexpectedly succeeded");
+    }
+
+    #[test]
+    fn segments_on_out_of_range_phoff_yields_err_instead_of_panicking() {
+        let mut data = minimal_elf32le();
+        data[28..32].copy_from_slice(&9999u32.to_le_bytes()); // e_phoff
+        data[42..44].copy_from_slice(&32u16.to_le_bytes());   // e_phentsize
+        data[44..46].copy_from_slice(&1u16.to_le_bytes());    // e_phnum
+
+        let elf = Elf::parse(&data).unwrap();
+        let mut segments = elf.segments();
+        segments.next().unwrap().expect_err(
+            "Parsing program headers at an out-of-range e_phoff unexpectedly succeeded");
+        assert!(segments.next().is_none());
+    }
+}
@@ -0,0 +1,14 @@
+extern crate posix;
+use posix::Errno;
+
+#[derive(Debug)]
+pub struct ElfParserError {
+    pub errno: Errno,
+    pub message: String,
+}
+
+impl ElfParserError {
+    pub fn new(errno: Errno, message: String) -> Self {
+        Self { errno, message }
+    }
+}
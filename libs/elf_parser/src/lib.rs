@@ -0,0 +1,15 @@
+extern crate posix;
+extern crate stpack;
+
+pub mod elf;
+pub mod err;
+pub mod ident;
+pub mod phdr;
+pub mod reloc;
+pub mod sections;
+pub mod string_table;
+pub mod symbol;
+pub mod symtab;
+
+pub use elf::Elf;
+pub use sections::ElfSection;
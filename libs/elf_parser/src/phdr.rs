@@ -0,0 +1,289 @@
+extern crate posix;
+use posix::Errno;
+
+extern crate stpack;
+use stpack::{unpacker, Unpacker};
+
+use crate::err::ElfParserError;
+use crate::ident::{ElfClass, ElfEndian};
+
+unpacker! {
+    pub struct Elf32Phdr {
+        pub p_type: u32,
+        pub p_offset: u32,
+        pub p_vaddr: u32,
+        pub p_paddr: u32,
+        pub p_filesz: u32,
+        pub p_memsz: u32,
+        pub p_flags: u32,
+        pub p_align: u32,
+    }
+}
+
+unpacker! {
+    pub struct Elf64Phdr {
+        pub p_type: u32,
+        pub p_flags: u32,
+        pub p_offset: u64,
+        pub p_vaddr: u64,
+        pub p_paddr: u64,
+        pub p_filesz: u64,
+        pub p_memsz: u64,
+        pub p_align: u64,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProgramHeader {
+    pub p_type: u32,
+    pub p_offset: u64,
+    pub p_vaddr: u64,
+    pub p_paddr: u64,
+    pub p_filesz: u64,
+    pub p_memsz: u64,
+    pub p_flags: u32,
+    pub p_align: u64,
+}
+
+pub struct ProgramHeaderIterator<'a> {
+    class: ElfClass,
+    le: bool,
+    content: &'a [u8],
+    phentsize: u64,
+    phnum: u64,
+    file_len: u64,
+    curr_idx: u64,
+}
+
+impl<'a> ProgramHeaderIterator<'a> {
+    pub(crate) fn new(class: ElfClass,
+                      endian: ElfEndian,
+                      content: &'a [u8],
+                      phentsize: u64,
+                      phnum: u64,
+                      file_len: u64) -> Self
+    {
+        Self {
+            class,
+            le: endian == ElfEndian::ElfLE,
+            content,
+            phentsize,
+            phnum,
+            file_len,
+            curr_idx: 0,
+        }
+    }
+}
+
+impl<'a> Iterator for ProgramHeaderIterator<'a> {
+    type Item = Result<ProgramHeader, ElfParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.curr_idx >= self.phnum {
+            return None;
+        }
+
+        if self.phentsize == 0 {
+            self.curr_idx = self.phnum;
+            return Some(Err(ElfParserError::new(
+                Errno::EINVAL, String::from("Program header entry size is 0 (file broken)"))));
+        }
+
+        let needed = match self.phentsize.checked_mul(self.phnum) {
+            Some(needed) => needed as usize,
+            None => {
+                self.curr_idx = self.phnum;
+                return Some(Err(ElfParserError::new(
+                    Errno::EINVAL, String::from("Program header table size overflows"))));
+            }
+        };
+
+        if self.content.len() < needed {
+            self.curr_idx = self.phnum;
+            return Some(Err(ElfParserError::new(
+                Errno::EINVAL,
+                format!("Program header table is truncated: need {} bytes, have {}",
+                        needed, self.content.len()))));
+        }
+
+        let data = &self.content[(self.phentsize as usize * self.curr_idx as usize)..];
+
+        let (p_type, p_offset, p_vaddr, p_paddr, p_filesz, p_memsz, p_flags, p_align) =
+            match self.class {
+                ElfClass::Elf32 =>
+                    match Elf32Phdr::unpack(data, self.le) {
+                        Ok((ent, _)) => (
+                            ent.p_type,
+                            ent.p_offset as u64,
+                            ent.p_vaddr as u64,
+                            ent.p_paddr as u64,
+                            ent.p_filesz as u64,
+                            ent.p_memsz as u64,
+                            ent.p_flags,
+                            ent.p_align as u64,
+                        ),
+                        Err(_) => return Some(Err(ElfParserError::new(
+                            Errno::EINVAL, String::from("Failed to parse program header entry")))),
+                    },
+                ElfClass::Elf64 =>
+                    match Elf64Phdr::unpack(data, self.le) {
+                        Ok((ent, _)) => (
+                            ent.p_type,
+                            ent.p_offset,
+                            ent.p_vaddr,
+                            ent.p_paddr,
+                            ent.p_filesz,
+                            ent.p_memsz,
+                            ent.p_flags,
+                            ent.p_align,
+                        ),
+                        Err(_) => return Some(Err(ElfParserError::new(
+                            Errno::EINVAL, String::from("Failed to parse program header entry")))),
+                    },
+            };
+
+        match p_offset.checked_add(p_filesz) {
+            Some(end) if end <= self.file_len => (),
+            Some(end) => {
+                self.curr_idx += 1;
+                return Some(Err(ElfParserError::new(
+                    Errno::EINVAL,
+                    format!("Program header p_offset ({}) + p_filesz ({}) = {} exceeds file length ({})",
+                            p_offset, p_filesz, end, self.file_len))));
+            }
+            None => {
+                self.curr_idx += 1;
+                return Some(Err(ElfParserError::new(
+                    Errno::EINVAL,
+                    format!("Program header p_offset ({}) + p_filesz ({}) overflows",
+                            p_offset, p_filesz))));
+            }
+        }
+
+        self.curr_idx += 1;
+
+        Some(Ok(ProgramHeader {
+            p_type,
+            p_offset,
+            p_vaddr,
+            p_paddr,
+            p_filesz,
+            p_memsz,
+            p_flags,
+            p_align,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        ident::{ElfClass, ElfEndian},
+        phdr::{ProgramHeaderIterator, ProgramHeader, Elf32Phdr, Elf64Phdr},
+    };
+
+    #[test]
+    fn elf32be_single_load_segment() {
+        let content: &[u8] = &[
+            0, 0, 0, 1,                 // p_type = PT_LOAD
+            0, 0, 0, 0x10,              // p_offset
+            0, 0, 0x10, 0x00,           // p_vaddr
+            0, 0, 0x10, 0x00,           // p_paddr
+            0, 0, 0, 0x20,              // p_filesz
+            0, 0, 0, 0x20,              // p_memsz
+            0, 0, 0, 5,                 // p_flags
+            0, 0, 0, 4,                 // p_align
+        ];
+
+        let mut iter = ProgramHeaderIterator::new(
+            ElfClass::Elf32, ElfEndian::ElfBE, content, Elf32Phdr::SIZE as u64, 1, 0x1000);
+
+        assert_eq!(
+            iter.next().unwrap().unwrap(),
+            ProgramHeader {
+                p_type: 1,
+                p_offset: 0x10,
+                p_vaddr: 0x1000,
+                p_paddr: 0x1000,
+                p_filesz: 0x20,
+                p_memsz: 0x20,
+                p_flags: 5,
+                p_align: 4,
+            }
+        );
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn elf64le_single_load_segment() {
+        let content: &[u8] = &[
+            1, 0, 0, 0,                 // p_type
+            5, 0, 0, 0,                 // p_flags
+            0x10, 0, 0, 0, 0, 0, 0, 0,  // p_offset
+            0, 0x10, 0, 0, 0, 0, 0, 0,  // p_vaddr
+            0, 0x10, 0, 0, 0, 0, 0, 0,  // p_paddr
+            0x20, 0, 0, 0, 0, 0, 0, 0,  // p_filesz
+            0x20, 0, 0, 0, 0, 0, 0, 0,  // p_memsz
+            4, 0, 0, 0, 0, 0, 0, 0,     // p_align
+        ];
+
+        let mut iter = ProgramHeaderIterator::new(
+            ElfClass::Elf64, ElfEndian::ElfLE, content, Elf64Phdr::SIZE as u64, 1, 0x1000);
+
+        assert_eq!(
+            iter.next().unwrap().unwrap(),
+            ProgramHeader {
+                p_type: 1,
+                p_offset: 0x10,
+                p_vaddr: 0x1000,
+                p_paddr: 0x1000,
+                p_filesz: 0x20,
+                p_memsz: 0x20,
+                p_flags: 5,
+                p_align: 4,
+            }
+        );
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn truncated_phdr_table() {
+        let content: &[u8] = &[0u8; 4];
+        let mut iter = ProgramHeaderIterator::new(
+            ElfClass::Elf32, ElfEndian::ElfBE, content, Elf32Phdr::SIZE as u64, 1, 0x1000);
+
+        iter.next().unwrap().expect_err(
+            "Parsing truncated program header table unexpectedly succeeded");
+    }
+
+    #[test]
+    fn zero_phentsize() {
+        let content: &[u8] = &[0u8; 32];
+        let mut iter = ProgramHeaderIterator::new(
+            ElfClass::Elf32, ElfEndian::ElfBE, content, 0, 1, 0x1000);
+
+        iter.next().unwrap().expect_err(
+            "Parsing program header table with zero entsize unexpectedly succeeded");
+    }
+
+    #[test]
+    fn segment_past_end_of_file_is_rejected() {
+        let content: &[u8] = &[
+            0, 0, 0, 1,                 // p_type = PT_LOAD
+            0, 0, 0, 0x10,              // p_offset
+            0, 0, 0x10, 0x00,           // p_vaddr
+            0, 0, 0x10, 0x00,           // p_paddr
+            0, 0, 0, 0x20,              // p_filesz
+            0, 0, 0, 0x20,              // p_memsz
+            0, 0, 0, 5,                 // p_flags
+            0, 0, 0, 4,                 // p_align
+        ];
+
+        // p_offset (0x10) + p_filesz (0x20) = 0x30, past a 16-byte file.
+        let mut iter = ProgramHeaderIterator::new(
+            ElfClass::Elf32, ElfEndian::ElfBE, content, Elf32Phdr::SIZE as u64, 1, 16);
+
+        iter.next().unwrap().expect_err(
+            "Parsing a segment extending past EOF unexpectedly succeeded");
+    }
+}
@@ -0,0 +1,458 @@
+extern crate posix;
+use posix::Errno;
+
+extern crate stpack;
+use stpack::{unpacker, Unpacker};
+
+use crate::ElfSection;
+use crate::err::ElfParserError;
+use crate::ident::{ElfClass, ElfEndian};
+use crate::string_table;
+use crate::symtab::{SHT_DYNSYM, SHT_STRTAB, SHT_SYMTAB};
+
+const SHT_REL: u32 = 9;
+const SHT_RELA: u32 = 4;
+
+unpacker! {
+    pub struct Elf32Rel {
+        pub r_offset: u32,
+        pub r_info: u32,
+    }
+}
+
+unpacker! {
+    pub struct Elf32Rela {
+        pub r_offset: u32,
+        pub r_info: u32,
+        pub r_addend: i32,
+    }
+}
+
+unpacker! {
+    pub struct Elf64Rel {
+        pub r_offset: u64,
+        pub r_info: u64,
+    }
+}
+
+unpacker! {
+    pub struct Elf64Rela {
+        pub r_offset: u64,
+        pub r_info: u64,
+        pub r_addend: i64,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Relocation<'a> {
+    pub offset: u64,
+    pub sym: u32,
+    pub typ: u32,
+    pub addend: Option<i64>,
+    pub symbol_name: &'a str,
+}
+
+pub struct RelocationIterator<'a> {
+    class: ElfClass,
+    le: bool,
+    sections: &'a Vec<ElfSection<'a>>,
+    curr_secidx: usize,
+    curr_relidx: usize,
+}
+
+impl<'a> RelocationIterator<'a> {
+    pub(crate) fn new(class: ElfClass,
+                      endian: ElfEndian,
+                      sections: &'a Vec<ElfSection<'a>>) -> Self
+    {
+        Self {
+            class,
+            le: endian == ElfEndian::ElfLE,
+            sections,
+            curr_secidx: 0,
+            curr_relidx: 0,
+        }
+    }
+}
+
+/// Resolves the name of symbol index `sym` in the symbol table section at
+/// `sections[symtab_secidx]`, following its `sh_link` to the associated
+/// string table (the same two-step lookup `SymtabIterator` does for the
+/// symbols it yields itself).
+///
+/// `st_name` is the first field of both `Elf32SymtabEntry` and
+/// `Elf64SymtabEntry`, so it can be read directly without knowing which
+/// class the entry belongs to.
+fn resolve_symbol_name<'a>(le: bool,
+                           sections: &'a [ElfSection<'a>],
+                           symtab_secidx: usize,
+                           sym: u32) -> Result<&'a str, ElfParserError>
+{
+    let symtab_sec = &sections[symtab_secidx];
+
+    if symtab_sec.entsize == 0 {
+        return Err(ElfParserError::new(
+            Errno::EINVAL, String::from("Symtab section entry size is 0 (file broken)")));
+    }
+
+    let symidx = sym as usize;
+    let symcnt = symtab_sec.content.len() / (symtab_sec.entsize as usize);
+    if symidx >= symcnt {
+        return Err(ElfParserError::new(
+            Errno::EINVAL,
+            format!("Relocation symbol index {} is out of range (symtab has {} entries)",
+                    symidx, symcnt)));
+    }
+
+    let data = &symtab_sec.content[(symtab_sec.entsize as usize * symidx)..];
+
+    let nameoff = match u32::unpack(data, le) {
+        Ok((name, _)) => name as usize,
+        Err(_) => return Err(ElfParserError::new(
+            Errno::EINVAL, String::from("Failed to parse symtab entry"))),
+    };
+
+    if symtab_sec.link as usize >= sections.len() {
+        return Err(ElfParserError::new(
+            Errno::EINVAL,
+            format!("Symtab refer invalid strtab section index: \
+                     {} (must be less than {})",
+                    symtab_sec.link, sections.len())));
+    }
+
+    let strtab_sec = &sections[symtab_sec.link as usize];
+    if strtab_sec.typ != SHT_STRTAB {
+        return Err(ElfParserError::new(
+            Errno::EINVAL,
+            format!("Symtab linked section is not SHT_STRTAB: {}", symtab_sec.link)));
+    }
+
+    Ok(string_table::read_str_from_offset(strtab_sec.content, nameoff))
+}
+
+impl<'a> Iterator for RelocationIterator<'a> {
+    type Item = Result<Relocation<'a>, ElfParserError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut secidx = self.curr_secidx;
+        let mut relidx = self.curr_relidx;
+        let seccnt = self.sections.len();
+
+        loop {
+            if secidx >= seccnt {
+                break;
+            }
+
+            let sec = &self.sections[secidx];
+            if sec.typ == SHT_REL || sec.typ == SHT_RELA {
+                if sec.entsize == 0 {
+                    return Some(Err(ElfParserError::new(
+                        Errno::EINVAL, String::from("Relocation section entry size is 0 (file broken)"))))
+                }
+
+                if relidx < (sec.content.len() / (sec.entsize as usize)) {
+                    break;
+                }
+            }
+
+            secidx += 1;
+            relidx = 0;
+        }
+
+        self.curr_secidx = secidx;
+        self.curr_relidx = relidx;
+
+        if secidx >= seccnt {
+            return None;
+        }
+
+        let sec = &self.sections[secidx];
+        let is_rela = sec.typ == SHT_RELA;
+        let data = &sec.content[(sec.entsize as usize * relidx)..];
+
+        let (offset, info, addend) = match (self.class, is_rela) {
+            (ElfClass::Elf32, false) =>
+                match Elf32Rel::unpack(data, self.le) {
+                    Ok((ent, _)) => (ent.r_offset as u64, ent.r_info as u64, None),
+                    Err(_) => return Some(Err(ElfParserError::new(
+                        Errno::EINVAL, String::from("Failed to parse relocation entry")))),
+                },
+            (ElfClass::Elf32, true) =>
+                match Elf32Rela::unpack(data, self.le) {
+                    Ok((ent, _)) => (ent.r_offset as u64, ent.r_info as u64, Some(ent.r_addend as i64)),
+                    Err(_) => return Some(Err(ElfParserError::new(
+                        Errno::EINVAL, String::from("Failed to parse relocation entry")))),
+                },
+            (ElfClass::Elf64, false) =>
+                match Elf64Rel::unpack(data, self.le) {
+                    Ok((ent, _)) => (ent.r_offset, ent.r_info, None),
+                    Err(_) => return Some(Err(ElfParserError::new(
+                        Errno::EINVAL, String::from("Failed to parse relocation entry")))),
+                },
+            (ElfClass::Elf64, true) =>
+                match Elf64Rela::unpack(data, self.le) {
+                    Ok((ent, _)) => (ent.r_offset, ent.r_info, Some(ent.r_addend)),
+                    Err(_) => return Some(Err(ElfParserError::new(
+                        Errno::EINVAL, String::from("Failed to parse relocation entry")))),
+                },
+        };
+
+        let (sym, typ) = match self.class {
+            ElfClass::Elf32 => ((info >> 8) as u32, (info & 0xff) as u32),
+            ElfClass::Elf64 => ((info >> 32) as u32, (info & 0xffffffff) as u32),
+        };
+
+        if sec.link as usize >= self.sections.len() {
+            return Some(Err(ElfParserError::new(
+                Errno::EINVAL,
+                format!("Relocation refers to invalid symtab section index: \
+                         {} (must be less than {})",
+                        sec.link, self.sections.len()))));
+        }
+
+        let symtab_secidx = sec.link as usize;
+        let symtab_sec = &self.sections[symtab_secidx];
+        if symtab_sec.typ != SHT_SYMTAB && symtab_sec.typ != SHT_DYNSYM {
+            return Some(Err(ElfParserError::new(
+                Errno::EINVAL,
+                format!("Relocation linked section is not SHT_SYMTAB or SHT_DYNSYM: {}", sec.link))));
+        }
+
+        let symbol_name = match resolve_symbol_name(
+            self.le, self.sections, symtab_secidx, sym)
+        {
+            Ok(name) => name,
+            Err(err) => return Some(Err(err)),
+        };
+
+        self.curr_relidx = relidx + 1;
+
+        Some(Ok(Relocation {
+            offset,
+            sym,
+            typ,
+            addend,
+            symbol_name,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use stpack::Unpacker;
+    use crate::{
+        ElfSection,
+        ident::{ElfClass, ElfEndian},
+        reloc::{RelocationIterator, Relocation, Elf32Rel, Elf32Rela, Elf64Rela},
+        symtab::{Elf32SymtabEntry, SHT_SYMTAB, SHT_STRTAB},
+        reloc::{SHT_REL, SHT_RELA},
+    };
+
+    fn symtab_and_strtab_sections() -> Vec<ElfSection<'static>> {
+        vec![
+            ElfSection {
+                name: "",
+                typ: SHT_SYMTAB,
+                flags: 0,
+                addr: 0,
+                link: 1,
+                info: 0,
+                addralign: 0,
+                entsize: Elf32SymtabEntry::SIZE as u64,
+                content: &[
+                    0, 0, 0, 0,                 // name
+                    0, 0, 0, 0,                 // value
+                    0, 0, 0, 0,                 // size
+                    0,                          // info
+                    0,                          // other
+                    0, 0,                       // shndx
+
+                    0, 0, 0, 1,                 // name
+                    0, 0, 0, 0,                 // value
+                    0, 0, 0, 0,                 // size
+                    0,                          // info
+                    0,                          // other
+                    0, 0,                       // shndx
+                ],
+            },
+
+            ElfSection {
+                name: "",
+                typ: SHT_STRTAB,
+                flags: 0,
+                addr: 0,
+                link: 0,
+                info: 0,
+                addralign: 0,
+                entsize: 0,
+                content: &[
+                    0,
+                    b's', b'y', b'm', 0,
+                ],
+            },
+        ]
+    }
+
+    #[test]
+    fn elf32be_rel_resolves_symbol_name() {
+        let mut sections = symtab_and_strtab_sections();
+        sections.push(ElfSection {
+            name: "",
+            typ: SHT_REL,
+            flags: 0,
+            addr: 0,
+            link: 0,
+            info: 0,
+            addralign: 0,
+            entsize: Elf32Rel::SIZE as u64,
+            content: &[
+                0, 0, 0x10, 0x00,           // r_offset
+                0, 0, 0x01, 0x03,           // r_info: sym = 1, type = 3
+            ],
+        });
+
+        assert_eq!(
+            RelocationIterator::new(ElfClass::Elf32, ElfEndian::ElfBE, &sections)
+                .map(|r| r.unwrap())
+                .collect::<Vec<Relocation>>(),
+            vec![
+                Relocation {
+                    offset: 0x1000,
+                    sym: 1,
+                    typ: 3,
+                    addend: None,
+                    symbol_name: "sym",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn elf32be_rela_carries_addend() {
+        let mut sections = symtab_and_strtab_sections();
+        sections.push(ElfSection {
+            name: "",
+            typ: SHT_RELA,
+            flags: 0,
+            addr: 0,
+            link: 0,
+            info: 0,
+            addralign: 0,
+            entsize: Elf32Rela::SIZE as u64,
+            content: &[
+                0, 0, 0x20, 0x00,           // r_offset
+                0, 0, 0x01, 0x02,           // r_info: sym = 1, type = 2
+                0xff, 0xff, 0xff, 0xfc,     // r_addend = -4
+            ],
+        });
+
+        assert_eq!(
+            RelocationIterator::new(ElfClass::Elf32, ElfEndian::ElfBE, &sections)
+                .map(|r| r.unwrap())
+                .collect::<Vec<Relocation>>(),
+            vec![
+                Relocation {
+                    offset: 0x2000,
+                    sym: 1,
+                    typ: 2,
+                    addend: Some(-4),
+                    symbol_name: "sym",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn elf64le_rela_decodes_wide_sym_and_type() {
+        let sections = vec![
+            ElfSection {
+                name: "",
+                typ: SHT_SYMTAB,
+                flags: 0,
+                addr: 0,
+                link: 1,
+                info: 0,
+                addralign: 0,
+                entsize: crate::symtab::Elf64SymtabEntry::SIZE as u64,
+                content: &[
+                    1, 0, 0, 0,                 // name
+                    0,                          // info
+                    0,                          // other
+                    0, 0,                       // shndx
+                    0, 0, 0, 0, 0, 0, 0, 0,     // value
+                    0, 0, 0, 0, 0, 0, 0, 0,     // size
+                ],
+            },
+
+            ElfSection {
+                name: "",
+                typ: SHT_STRTAB,
+                flags: 0,
+                addr: 0,
+                link: 0,
+                info: 0,
+                addralign: 0,
+                entsize: 0,
+                content: &[
+                    0,
+                    b's', b'y', b'm', 0,
+                ],
+            },
+
+            ElfSection {
+                name: "",
+                typ: SHT_RELA,
+                flags: 0,
+                addr: 0,
+                link: 0,
+                info: 0,
+                addralign: 0,
+                entsize: Elf64Rela::SIZE as u64,
+                content: &[
+                    0x10, 0, 0, 0, 0, 0, 0, 0,                         // r_offset
+                    7, 0, 0, 0, 0, 0, 0, 0,                            // r_info: type = 7, sym = 0
+                    0x05, 0, 0, 0, 0, 0, 0, 0,                         // r_addend
+                ],
+            },
+        ];
+
+        assert_eq!(
+            RelocationIterator::new(ElfClass::Elf64, ElfEndian::ElfLE, &sections)
+                .map(|r| r.unwrap())
+                .collect::<Vec<Relocation>>(),
+            vec![
+                Relocation {
+                    offset: 0x10,
+                    sym: 0,
+                    typ: 7,
+                    addend: Some(5),
+                    symbol_name: "sym",
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_symtab_link() {
+        let sections = vec![
+            ElfSection {
+                name: "",
+                typ: SHT_REL,
+                flags: 0,
+                addr: 0,
+                link: 5,
+                info: 0,
+                addralign: 0,
+                entsize: Elf32Rel::SIZE as u64,
+                content: &[
+                    0, 0, 0, 0,
+                    0, 0, 0, 0,
+                ],
+            },
+        ];
+
+        let mut iter = RelocationIterator::new(ElfClass::Elf32, ElfEndian::ElfBE, &sections);
+        iter.next().unwrap().expect_err(
+            "Parsing relocation with out-of-range sh_link unexpectedly succeeded");
+    }
+}
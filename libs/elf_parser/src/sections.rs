@@ -0,0 +1,165 @@
+extern crate posix;
+use posix::Errno;
+
+extern crate stpack;
+use stpack::{unpacker, Unpacker};
+
+use crate::err::ElfParserError;
+use crate::ident::{ElfClass, ElfEndian};
+use crate::string_table;
+
+unpacker! {
+    pub struct Elf32Shdr {
+        pub name: u32,
+        pub typ: u32,
+        pub flags: u32,
+        pub addr: u32,
+        pub offset: u32,
+        pub size: u32,
+        pub link: u32,
+        pub info: u32,
+        pub addralign: u32,
+        pub entsize: u32,
+    }
+}
+
+unpacker! {
+    pub struct Elf64Shdr {
+        pub name: u32,
+        pub typ: u32,
+        pub flags: u64,
+        pub addr: u64,
+        pub offset: u64,
+        pub size: u64,
+        pub link: u32,
+        pub info: u32,
+        pub addralign: u64,
+        pub entsize: u64,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ElfSection<'a> {
+    pub name: &'a str,
+    pub typ: u32,
+    pub flags: u64,
+    pub addr: u64,
+    pub link: u32,
+    pub info: u32,
+    pub addralign: u64,
+    pub entsize: u64,
+    pub content: &'a [u8],
+}
+
+struct RawShdr {
+    name_off: usize,
+    typ: u32,
+    flags: u64,
+    addr: u64,
+    offset: u64,
+    size: u64,
+    link: u32,
+    info: u32,
+    addralign: u64,
+    entsize: u64,
+}
+
+/// Parses the section header table starting at `shoff` in `data` and
+/// resolves every section's name through the `e_shstrndx` string table.
+pub(crate) fn parse(data: &[u8],
+                    class: ElfClass,
+                    endian: ElfEndian,
+                    shoff: u64,
+                    shnum: u16,
+                    shentsize: u16,
+                    shstrndx: u16) -> Result<Vec<ElfSection<'_>>, ElfParserError>
+{
+    let le = endian == ElfEndian::ElfLE;
+
+    if shentsize == 0 {
+        return Err(ElfParserError::new(
+            Errno::EINVAL, String::from("Section header entry size is 0 (file broken)")));
+    }
+
+    let shoff = shoff as usize;
+    let needed = shentsize as usize * shnum as usize;
+    let end = shoff.checked_add(needed).ok_or_else(|| ElfParserError::new(
+        Errno::EINVAL,
+        format!("Section header table overflows: offset {} + {} bytes", shoff, needed)))?;
+    let shtab = data.get(shoff..end).ok_or_else(|| ElfParserError::new(
+        Errno::EINVAL,
+        format!("Section header table is truncated: need {} bytes at offset {}",
+                needed, shoff)))?;
+
+    let mut raws = Vec::with_capacity(shnum as usize);
+    for i in 0..shnum as usize {
+        let entry = &shtab[(i * shentsize as usize)..];
+        let raw = match class {
+            ElfClass::Elf32 => match Elf32Shdr::unpack(entry, le) {
+                Ok((sh, _)) => RawShdr {
+                    name_off: sh.name as usize,
+                    typ: sh.typ,
+                    flags: sh.flags as u64,
+                    addr: sh.addr as u64,
+                    offset: sh.offset as u64,
+                    size: sh.size as u64,
+                    link: sh.link,
+                    info: sh.info,
+                    addralign: sh.addralign as u64,
+                    entsize: sh.entsize as u64,
+                },
+                Err(_) => return Err(ElfParserError::new(
+                    Errno::EINVAL, String::from("Failed to parse section header entry"))),
+            },
+            ElfClass::Elf64 => match Elf64Shdr::unpack(entry, le) {
+                Ok((sh, _)) => RawShdr {
+                    name_off: sh.name as usize,
+                    typ: sh.typ,
+                    flags: sh.flags,
+                    addr: sh.addr,
+                    offset: sh.offset,
+                    size: sh.size,
+                    link: sh.link,
+                    info: sh.info,
+                    addralign: sh.addralign,
+                    entsize: sh.entsize,
+                },
+                Err(_) => return Err(ElfParserError::new(
+                    Errno::EINVAL, String::from("Failed to parse section header entry"))),
+            },
+        };
+        raws.push(raw);
+    }
+
+    let shstrtab = raws.get(shstrndx as usize).ok_or_else(|| ElfParserError::new(
+        Errno::EINVAL,
+        format!("e_shstrndx ({}) is out of range (must be less than {})",
+                shstrndx, raws.len())))?;
+    let shstrtab_start = shstrtab.offset as usize;
+    let shstrtab_end = shstrtab_start.checked_add(shstrtab.size as usize)
+        .ok_or_else(|| ElfParserError::new(
+            Errno::EINVAL, String::from("Section header string table size overflows")))?;
+    let shstrtab_content = data.get(shstrtab_start..shstrtab_end)
+        .ok_or_else(|| ElfParserError::new(
+            Errno::EINVAL, String::from("Section header string table is out of bounds")))?;
+
+    raws.into_iter().map(|raw| {
+        let start = raw.offset as usize;
+        let end = start.checked_add(raw.size as usize).ok_or_else(|| ElfParserError::new(
+            Errno::EINVAL, String::from("Section size overflows")))?;
+        let content = data.get(start..end).ok_or_else(|| ElfParserError::new(
+            Errno::EINVAL, format!("Section content is out of bounds: {}..{}", start, end)))?;
+
+        Ok(ElfSection {
+            name: string_table::read_str_from_offset(shstrtab_content, raw.name_off),
+            typ: raw.typ,
+            flags: raw.flags,
+            addr: raw.addr,
+            link: raw.link,
+            info: raw.info,
+            addralign: raw.addralign,
+            entsize: raw.entsize,
+            content,
+        })
+    }).collect()
+}
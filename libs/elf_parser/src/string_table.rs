@@ -0,0 +1,5 @@
+pub fn read_str_from_offset(content: &[u8], offset: usize) -> &str {
+    let bytes = content.get(offset..).unwrap_or(&[]);
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    core::str::from_utf8(&bytes[..end]).unwrap_or("")
+}
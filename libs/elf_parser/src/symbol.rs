@@ -0,0 +1,115 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolBinding {
+    Local,
+    Global,
+    Weak,
+    Other(u8),
+}
+
+impl SymbolBinding {
+    fn from_raw(raw: u8) -> Self {
+        match raw {
+            0 => SymbolBinding::Local,
+            1 => SymbolBinding::Global,
+            2 => SymbolBinding::Weak,
+            other => SymbolBinding::Other(other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolType {
+    NoType,
+    Object,
+    Func,
+    Section,
+    File,
+    Other(u8),
+}
+
+impl SymbolType {
+    fn from_raw(raw: u8) -> Self {
+        match raw {
+            0 => SymbolType::NoType,
+            1 => SymbolType::Object,
+            2 => SymbolType::Func,
+            3 => SymbolType::Section,
+            4 => SymbolType::File,
+            other => SymbolType::Other(other),
+        }
+    }
+}
+
+/// Which section a `Symbol` was read out of: the static symbol table
+/// (`SHT_SYMTAB`) or the dynamic one (`SHT_DYNSYM`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymtabKind {
+    Symtab,
+    Dynsym,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol<'a> {
+    pub name: &'a str,
+    pub value: u64,
+    pub size: u64,
+    pub info: u8,
+    pub other: u8,
+    pub shndx: u16,
+    pub table: SymtabKind,
+}
+
+impl<'a> Symbol<'a> {
+    pub fn binding(&self) -> SymbolBinding {
+        SymbolBinding::from_raw(self.info >> 4)
+    }
+
+    pub fn typ(&self) -> SymbolType {
+        SymbolType::from_raw(self.info & 0xf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::symbol::{Symbol, SymbolBinding, SymbolType, SymtabKind};
+
+    fn symbol_with_info(info: u8) -> Symbol<'static> {
+        Symbol {
+            name: "test",
+            value: 0,
+            size: 0,
+            info,
+            other: 0,
+            shndx: 0,
+            table: SymtabKind::Symtab,
+        }
+    }
+
+    #[test]
+    fn decodes_global_func() {
+        let sym = symbol_with_info((1 << 4) | 2);
+        assert_eq!(sym.binding(), SymbolBinding::Global);
+        assert_eq!(sym.typ(), SymbolType::Func);
+    }
+
+    #[test]
+    fn decodes_local_notype() {
+        let sym = symbol_with_info(0);
+        assert_eq!(sym.binding(), SymbolBinding::Local);
+        assert_eq!(sym.typ(), SymbolType::NoType);
+    }
+
+    #[test]
+    fn decodes_weak_object() {
+        let sym = symbol_with_info((2 << 4) | 1);
+        assert_eq!(sym.binding(), SymbolBinding::Weak);
+        assert_eq!(sym.typ(), SymbolType::Object);
+    }
+
+    #[test]
+    fn decodes_unknown_binding_and_type() {
+        let sym = symbol_with_info((7 << 4) | 13);
+        assert_eq!(sym.binding(), SymbolBinding::Other(7));
+        assert_eq!(sym.typ(), SymbolType::Other(13));
+    }
+}
@@ -8,10 +8,11 @@ use crate::ElfSection;
 use crate::err::ElfParserError;
 use crate::ident::{ElfClass, ElfEndian};
 use crate::string_table;
-use crate::symbol::Symbol;
+use crate::symbol::{Symbol, SymbolType, SymtabKind};
 
-const SHT_SYMTAB: u32 = 2;
-const SHT_STRTAB: u32 = 3;
+pub(crate) const SHT_SYMTAB: u32 = 2;
+pub(crate) const SHT_STRTAB: u32 = 3;
+pub(crate) const SHT_DYNSYM: u32 = 11;
 
 unpacker! {
     pub struct Elf32SymtabEntry {
@@ -39,6 +40,7 @@ pub struct SymtabIterator<'a> {
     class: ElfClass,
     le: bool,
     sections: &'a Vec<ElfSection<'a>>,
+    kind: Option<SymtabKind>,
     curr_secidx: usize,
     curr_symidx: usize,
 }
@@ -52,10 +54,50 @@ impl<'a> SymtabIterator<'a> {
             class,
             le: endian == ElfEndian::ElfLE,
             sections,
+            kind: None,
             curr_secidx: 0,
             curr_symidx: 0,
         }
     }
+
+    /// Like `new`, but restricts the scan to a single kind of symbol table
+    /// (e.g. callers wanting only `SHT_SYMTAB` can opt out of `SHT_DYNSYM`).
+    pub(crate) fn new_of_kind(class: ElfClass,
+                              endian: ElfEndian,
+                              sections: &'a Vec<ElfSection<'a>>,
+                              kind: SymtabKind) -> Self
+    {
+        Self {
+            kind: Some(kind),
+            ..Self::new(class, endian, sections)
+        }
+    }
+
+    pub fn filter_type(self, typ: SymbolType) -> impl Iterator<Item = Result<Symbol<'a>, ElfParserError>> {
+        self.filter(move |item| match item {
+            Ok(sym) => sym.typ() == typ,
+            Err(_) => true,
+        })
+    }
+
+    pub fn functions_only(self) -> impl Iterator<Item = Result<Symbol<'a>, ElfParserError>> {
+        self.filter_type(SymbolType::Func)
+    }
+
+    /// Returns which `SymtabKind` `sectyp` is, if any, restricted to `wanted`
+    /// when it is `Some`.
+    fn section_kind(sectyp: u32, wanted: Option<SymtabKind>) -> Option<SymtabKind> {
+        let kind = match sectyp {
+            SHT_SYMTAB => SymtabKind::Symtab,
+            SHT_DYNSYM => SymtabKind::Dynsym,
+            _ => return None,
+        };
+
+        match wanted {
+            Some(wanted) if wanted != kind => None,
+            _ => Some(kind),
+        }
+    }
 }
 
 impl<'a> Iterator for SymtabIterator<'a> {
@@ -72,7 +114,7 @@ impl<'a> Iterator for SymtabIterator<'a> {
             }
 
             let sec = &self.sections[secidx];
-            if sec.typ == SHT_SYMTAB {
+            if Self::section_kind(sec.typ, self.kind).is_some() {
                 if sec.entsize == 0 {
                     return Some(Err(ElfParserError::new(
                         Errno::EINVAL, String::from("Symtab section entry size is 0 (file broken)"))))
@@ -146,6 +188,9 @@ impl<'a> Iterator for SymtabIterator<'a> {
 
         self.curr_symidx = symidx + 1;
 
+        let table = Self::section_kind(sec.typ, self.kind)
+            .expect("section already matched by the scan loop above");
+
         Some(Ok(Symbol {
             name,
             value,
@@ -153,6 +198,7 @@ impl<'a> Iterator for SymtabIterator<'a> {
             info,
             other,
             shndx,
+            table,
         }))
     }
 }
@@ -171,9 +217,9 @@ mod tests {
             Elf64SymtabEntry,
             SHT_SYMTAB,
             SHT_STRTAB,
+            SHT_DYNSYM,
         },
-        symbol::Symbol,
-        stpack::Unpacker,
+        symbol::{Symbol, SymtabKind},
     };
 
     #[test]
@@ -242,6 +288,7 @@ mod tests {
                     info: 0,
                     other: 0,
                     shndx: 0,
+                    table: SymtabKind::Symtab,
                 },
             ]
         );
@@ -447,6 +494,7 @@ mod tests {
                     info: 0,
                     other: 0,
                     shndx: 0,
+                    table: SymtabKind::Symtab,
                 },
             ]
         );
@@ -549,4 +597,167 @@ mod tests {
         iter.next().unwrap().expect_err(
             "Parsing broken symtab unexpectedly succeed");
     }
+
+    #[test]
+    fn functions_only_skips_non_func_symbols() {
+        let sections = vec![
+
+            ElfSection {
+                name: "",
+                typ: SHT_SYMTAB,
+                flags: 0,
+                addr: 0,
+                link: 1,
+                info: 0,
+                addralign: 0,
+                entsize: Elf32SymtabEntry::SIZE as u64,
+                content: &[
+                    0, 0, 0, 1,                 // name
+                    0, 0, 0, 0,                 // addr
+                    0, 0, 0, 0,                 // size
+                    1,                          // info: STT_OBJECT
+                    0,                          // other
+                    0, 0,                       // shndx
+
+                    0, 0, 0, 5,                 // name
+                    0, 0, 0, 0,                 // addr
+                    0, 0, 0, 0,                 // size
+                    2,                          // info: STT_FUNC
+                    0,                          // other
+                    0, 0,                       // shndx
+                ],
+            },
+
+            ElfSection {
+                name: "",
+                typ: SHT_STRTAB,
+                flags: 0,
+                addr: 0,
+                link: 0,
+                info: 0,
+                addralign: 0,
+                entsize: 0,
+                content: &[
+                    0,
+                    b'v', b'a', b'r', 0,
+                    b'f', b'u', b'n', b'c', 0,
+                ],
+            },
+
+        ];
+
+        let names: Vec<&str> =
+            SymtabIterator::new(ElfClass::Elf32, ElfEndian::ElfBE, &sections)
+                .functions_only()
+                .map(|r| r.unwrap().name)
+                .collect();
+
+        assert_eq!(names, vec!["func"]);
+    }
+
+    #[test]
+    fn reads_symbols_from_dynsym_section() {
+        let sections = vec![
+
+            ElfSection {
+                name: "",
+                typ: SHT_DYNSYM,
+                flags: 0,
+                addr: 0,
+                link: 1,
+                info: 0,
+                addralign: 0,
+                entsize: Elf32SymtabEntry::SIZE as u64,
+                content: &[
+                    0, 0, 0, 1,                 // name
+                    0x11, 0x22, 0x33, 0x44,     // addr
+                    0, 0, 0, 0,                 // size
+                    0,                          // info
+                    0,                          // other
+                    0, 0,                       // shndx
+                ],
+            },
+
+            ElfSection {
+                name: "",
+                typ: SHT_STRTAB,
+                flags: 0,
+                addr: 0,
+                link: 0,
+                info: 0,
+                addralign: 0,
+                entsize: 0,
+                content: &[
+                    0,
+                    b't', b'e', b's', b't', 0,
+                ],
+            },
+
+        ];
+
+        assert_eq!(
+            SymtabIterator::new(ElfClass::Elf32, ElfEndian::ElfBE, &sections)
+                .map(|r| r.unwrap())
+                .collect::<Vec<Symbol>>(),
+            vec![
+                Symbol {
+                    name: "test",
+                    value: 0x11223344u64,
+                    size: 0,
+                    info: 0,
+                    other: 0,
+                    shndx: 0,
+                    table: SymtabKind::Dynsym,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn symtab_only_skips_dynsym_section() {
+        let sections = vec![
+
+            ElfSection {
+                name: "",
+                typ: SHT_DYNSYM,
+                flags: 0,
+                addr: 0,
+                link: 1,
+                info: 0,
+                addralign: 0,
+                entsize: Elf32SymtabEntry::SIZE as u64,
+                content: &[
+                    0, 0, 0, 1,                 // name
+                    0x11, 0x22, 0x33, 0x44,     // addr
+                    0, 0, 0, 0,                 // size
+                    0,                          // info
+                    0,                          // other
+                    0, 0,                       // shndx
+                ],
+            },
+
+            ElfSection {
+                name: "",
+                typ: SHT_STRTAB,
+                flags: 0,
+                addr: 0,
+                link: 0,
+                info: 0,
+                addralign: 0,
+                entsize: 0,
+                content: &[
+                    0,
+                    b't', b'e', b's', b't', 0,
+                ],
+            },
+
+        ];
+
+        let count =
+            SymtabIterator::new_of_kind(
+                ElfClass::Elf32, ElfEndian::ElfBE, &sections, SymtabKind::Symtab)
+                .count();
+
+        assert_eq!(count, 0);
+    }
 }
\ No newline at end of file
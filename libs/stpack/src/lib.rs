@@ -1,19 +1,66 @@
+/// Common interface implemented both by the primitive integer types and by
+/// every struct generated through the `unpacker!` macro, so that a field can
+/// be unpacked/packed the same way whether it is a scalar or a nested struct.
+#[allow(clippy::result_unit_err)]
+pub trait Unpacker: Sized {
+    const SIZE: usize;
+    fn unpack(data: &[u8], le: bool) -> Result<(Self, &[u8]), ()>;
+    fn pack(&self, buf: &mut [u8], le: bool) -> Result<usize, ()>;
+}
+
+macro_rules! impl_unpacker_for_int {
+    ($($ftyp:ty),*) => {
+        $(
+            impl Unpacker for $ftyp {
+                const SIZE: usize = core::mem::size_of::<$ftyp>();
+
+                fn unpack(data: &[u8], le: bool) -> Result<(Self, &[u8]), ()> {
+                    if data.len() < Self::SIZE {
+                        Err(())
+                    } else {
+                        let (data, right) = data.split_at(Self::SIZE);
+                        let bytes = <[u8; core::mem::size_of::<$ftyp>()]>::try_from(data).unwrap();
+                        Ok((
+                            if le { Self::from_le_bytes(bytes) } else { Self::from_be_bytes(bytes) },
+                            right
+                        ))
+                    }
+                }
+
+                fn pack(&self, buf: &mut [u8], le: bool) -> Result<usize, ()> {
+                    if buf.len() < Self::SIZE {
+                        Err(())
+                    } else {
+                        buf[..Self::SIZE].copy_from_slice(
+                            &if le { self.to_le_bytes() } else { self.to_be_bytes() });
+                        Ok(Self::SIZE)
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_unpacker_for_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
 #[macro_export]
 macro_rules! unpacker {
-    {@constructor_from <le> $ftyp:ty} => {
-        <$ftyp>::from_le_bytes
-    };
+    {@le_bool <le>} => { true };
+    {@le_bool <be>} => { false };
 
-    {@constructor_from <be> $ftyp:ty} => {
-        <$ftyp>::from_be_bytes
-    };
+    {@constructor_one <$lebe:ident> $data:ident, [u8; $n:expr] { $($p:tt)* }} => {{
+        let off = unpacker!{@allsize $($p)*};
+        let mut arr = [0u8; $n];
+        arr.copy_from_slice(&$data[off..off + $n]);
+        arr
+    }};
 
     {@constructor_one <$lebe:ident> $data:ident, $ftyp:ty { $($p:tt)* }} => {
-        unpacker!{@constructor_from <$lebe> $ftyp}
-        (<[u8; core::mem::size_of::<$ftyp>()]>::try_from(
+        <$ftyp as $crate::Unpacker>::unpack(
             &$data[(unpacker!{@allsize $($p)*})..
-                   (unpacker!{@allsize $($p)*}+(core::mem::size_of::<$ftyp>()))]
-        ).unwrap())
+                   (unpacker!{@allsize $($p)*}+(<$ftyp as $crate::Unpacker>::SIZE))],
+            unpacker!{@le_bool <$lebe>}
+        ).unwrap().0
     };
 
     {@constructor <$lebe:ident> $data:ident
@@ -27,15 +74,36 @@ macro_rules! unpacker {
     {@constructor <$lebe:ident> $data:ident
      { $($result:tt)* },
      { $($p:tt)* },
-     { $fname:ident : $ftyp:ty }} =>
+     { $fvis:vis $fname:ident : [u8; $n:expr] }} =>
     {
-        unpacker!{@constructor <$lebe> $data {$($result)*}, {$($p)*}, {$fname : $ftyp,}}
+        unpacker!{@constructor <$lebe> $data {$($result)*}, {$($p)*}, {$fvis $fname : [u8; $n],}}
     };
 
     {@constructor <$lebe:ident> $data:ident
      { $($result:tt)* },
      { $($p:tt)* },
-     { $fname:ident : $ftyp:ty, $($body:tt)* }} =>
+     { $fvis:vis $fname:ident : [u8; $n:expr], $($body:tt)* }} =>
+    {
+        unpacker!{
+            @constructor <$lebe> $data
+            {$($result)*
+             $fname: unpacker!{@constructor_one <$lebe> $data, [u8; $n] { $($p)* }},},
+            {$($p)* $fname : [u8; $n], },
+            { $($body)* }}
+    };
+
+    {@constructor <$lebe:ident> $data:ident
+     { $($result:tt)* },
+     { $($p:tt)* },
+     { $fvis:vis $fname:ident : $ftyp:ty }} =>
+    {
+        unpacker!{@constructor <$lebe> $data {$($result)*}, {$($p)*}, {$fvis $fname : $ftyp,}}
+    };
+
+    {@constructor <$lebe:ident> $data:ident
+     { $($result:tt)* },
+     { $($p:tt)* },
+     { $fvis:vis $fname:ident : $ftyp:ty, $($body:tt)* }} =>
     {
         unpacker!{
             @constructor <$lebe> $data
@@ -45,25 +113,77 @@ macro_rules! unpacker {
             { $($body)* }}
     };
 
+    {@serializer_one <$lebe:ident> $self_:ident, $buf:ident, $fname:ident : [u8; $n:expr] { $($p:tt)* }} => {
+        {
+            let off = unpacker!{@allsize $($p)*};
+            $buf[off..off + $n].copy_from_slice(&$self_.$fname);
+        }
+    };
+
+    {@serializer_one <$lebe:ident> $self_:ident, $buf:ident, $fname:ident : $ftyp:ty { $($p:tt)* }} => {
+        {
+            let off = unpacker!{@allsize $($p)*};
+            <$ftyp as $crate::Unpacker>::pack(
+                &$self_.$fname,
+                &mut $buf[off..off + <$ftyp as $crate::Unpacker>::SIZE],
+                unpacker!{@le_bool <$lebe>}
+            ).unwrap();
+        }
+    };
+
+    {@serializer <$lebe:ident> $self_:ident $buf:ident
+     { $($p:tt)* },
+     { }} => {};
+
+    {@serializer <$lebe:ident> $self_:ident $buf:ident
+     { $($p:tt)* },
+     { $fvis:vis $fname:ident : [u8; $n:expr] }} => {
+        unpacker!{@serializer <$lebe> $self_ $buf {$($p)*}, {$fvis $fname : [u8; $n],}}
+    };
+
+    {@serializer <$lebe:ident> $self_:ident $buf:ident
+     { $($p:tt)* },
+     { $fvis:vis $fname:ident : [u8; $n:expr], $($body:tt)* }} => {
+        unpacker!{@serializer_one <$lebe> $self_, $buf, $fname : [u8; $n] { $($p)* }};
+        unpacker!{@serializer <$lebe> $self_ $buf {$($p)* $fname : [u8; $n], }, { $($body)* }}
+    };
+
+    {@serializer <$lebe:ident> $self_:ident $buf:ident
+     { $($p:tt)* },
+     { $fvis:vis $fname:ident : $ftyp:ty }} => {
+        unpacker!{@serializer <$lebe> $self_ $buf {$($p)*}, {$fvis $fname : $ftyp,}}
+    };
+
+    {@serializer <$lebe:ident> $self_:ident $buf:ident
+     { $($p:tt)* },
+     { $fvis:vis $fname:ident : $ftyp:ty, $($body:tt)* }} => {
+        unpacker!{@serializer_one <$lebe> $self_, $buf, $fname : $ftyp { $($p)* }};
+        unpacker!{@serializer <$lebe> $self_ $buf {$($p)* $fname : $ftyp, }, { $($body)* }}
+    };
+
     {@allsize} => {
         0
     };
 
-    {@allsize $fname:ident : $ftyp:ty} => {
-        unpacker!{@allsize $fname : $ftyp,}
+    {@allsize $fvis:vis $fname:ident : [u8; $n:expr]} => {
+        unpacker!{@allsize $fname : [u8; $n],}
+    };
+
+    {@allsize $fvis:vis $fname:ident : [u8; $n:expr], $($body:tt)*} => {
+        $n + unpacker!{@allsize $($body)*}
     };
 
-    {@allsize $fname:ident : $ftyp:ty, $($body:tt)*} => {
-        core::mem::size_of::<$ftyp>() + unpacker!{@allsize $($body)*}
+    {@allsize $fvis:vis $fname:ident : $ftyp:ty} => {
+        unpacker!{@allsize $fname : $ftyp,}
     };
 
-    {$(#[$attr:meta])* pub struct $stname:ident { $($body:tt)* }} => {
-        $(#[$attr])* pub unpacker!{struct $stname { $($body)* }}
+    {@allsize $fvis:vis $fname:ident : $ftyp:ty, $($body:tt)*} => {
+        <$ftyp as $crate::Unpacker>::SIZE + unpacker!{@allsize $($body)*}
     };
 
-    {$(#[$attr:meta])* struct $stname:ident { $($body:tt)* }} => {
+    {$(#[$attr:meta])* $vis:vis struct $stname:ident { $($body:tt)* }} => {
         $(#[$attr])*
-        struct $stname { $($body)* }
+        $vis struct $stname { $($body)* }
         impl $stname {
             const SIZE: usize = unpacker!{@allsize $($body)*};
 
@@ -90,6 +210,36 @@ macro_rules! unpacker {
                     ))
                 }
             }
+
+            fn pack_le(&self, buf: &mut [u8]) -> Result<usize, ()> {
+                if buf.len() < Self::SIZE {
+                    Err(())
+                } else {
+                    unpacker!{@serializer <le> self buf { }, { $($body)* }}
+                    Ok(Self::SIZE)
+                }
+            }
+
+            fn pack_be(&self, buf: &mut [u8]) -> Result<usize, ()> {
+                if buf.len() < Self::SIZE {
+                    Err(())
+                } else {
+                    unpacker!{@serializer <be> self buf { }, { $($body)* }}
+                    Ok(Self::SIZE)
+                }
+            }
+        }
+
+        impl $crate::Unpacker for $stname {
+            const SIZE: usize = <$stname>::SIZE;
+
+            fn unpack(data: &[u8], le: bool) -> Result<(Self, &[u8]), ()> {
+                if le { Self::unpack_le(data) } else { Self::unpack_be(data) }
+            }
+
+            fn pack(&self, buf: &mut [u8], le: bool) -> Result<usize, ()> {
+                if le { self.pack_le(buf) } else { self.pack_be(buf) }
+            }
         }
     };
 }
@@ -105,6 +255,30 @@ mod tests {
         }
     }
 
+    unpacker! {
+        #[derive(PartialEq, Eq, Debug)]
+        struct WithArray {
+            ident: [u8; 4],
+            value: u16,
+        }
+    }
+
+    unpacker! {
+        #[derive(PartialEq, Eq, Debug)]
+        struct WithNested {
+            foo: Foo,
+            tag: u8,
+        }
+    }
+
+    unpacker! {
+        #[derive(PartialEq, Eq, Debug)]
+        pub struct WithPubFields {
+            pub foo: u8,
+            pub bar: u16,
+        }
+    }
+
     #[test]
     fn foo_size() {
         assert_eq!(Foo::SIZE, 7);
@@ -141,4 +315,83 @@ mod tests {
             ))
         );
     }
+
+    #[test]
+    fn foo_pack_le_roundtrip() {
+        let data: Vec<u8> = (0..7).collect();
+        let (foo, _) = Foo::unpack_le(&data).unwrap();
+
+        let mut buf = [0u8; Foo::SIZE];
+        assert_eq!(foo.pack_le(&mut buf), Ok(Foo::SIZE));
+        assert_eq!(&buf[..], &data[..]);
+    }
+
+    #[test]
+    fn foo_pack_be_roundtrip() {
+        let data: Vec<u8> = (0..7).collect();
+        let (foo, _) = Foo::unpack_be(&data).unwrap();
+
+        let mut buf = [0u8; Foo::SIZE];
+        assert_eq!(foo.pack_be(&mut buf), Ok(Foo::SIZE));
+        assert_eq!(&buf[..], &data[..]);
+    }
+
+    #[test]
+    fn foo_pack_le_buffer_too_small() {
+        let foo = Foo { foo: 1, bar: 2, baz: 3 };
+        let mut buf = [0u8; Foo::SIZE - 1];
+        assert_eq!(foo.pack_le(&mut buf), Err(()));
+    }
+
+    #[test]
+    fn with_array_size() {
+        assert_eq!(WithArray::SIZE, 6);
+    }
+
+    #[test]
+    fn with_array_le() {
+        let data: Vec<u8> = (0..6).collect();
+        assert_eq!(
+            WithArray::unpack_le(&data),
+            Ok((
+                WithArray {
+                    ident: [0, 1, 2, 3],
+                    value: 0x0504,
+                },
+                &[] as &[u8]
+            ))
+        );
+    }
+
+    #[test]
+    fn with_nested_size() {
+        assert_eq!(WithNested::SIZE, Foo::SIZE + 1);
+    }
+
+    #[test]
+    fn with_nested_le() {
+        let data: Vec<u8> = (0..8).collect();
+        assert_eq!(
+            WithNested::unpack_le(&data),
+            Ok((
+                WithNested {
+                    foo: Foo { foo: 0x00, bar: 0x0201, baz: 0x06050403 },
+                    tag: 7,
+                },
+                &[] as &[u8]
+            ))
+        );
+    }
+
+    #[test]
+    fn with_pub_fields_le() {
+        let data: Vec<u8> = (0..3).collect();
+        assert_eq!(
+            WithPubFields::unpack_le(&data),
+            Ok((
+                WithPubFields { foo: 0x00, bar: 0x0201 },
+                &[] as &[u8]
+            ))
+        );
+    }
 }